@@ -0,0 +1,44 @@
+use std::hash::Hasher;
+
+/// A hashing strategy used to build and verify a Merkle tree.
+///
+/// An `Algorithm` is a `Hasher` that additionally knows how to turn raw bytes
+/// into a leaf hash and how to combine two child hashes into their parent.
+pub trait Algorithm<T>: Default + Hasher {
+    /// Finalize the current hasher state into a hash value.
+    fn hash(&mut self) -> T;
+
+    /// Reset the hasher state so it is ready to compute another hash.
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Turn an already-hashed value into a leaf. The default implementation
+    /// passes the value through unchanged.
+    fn leaf(&mut self, leaf: T) -> T {
+        leaf
+    }
+
+    /// Combine two child hashes into their parent hash.
+    fn node(&mut self, left: T, right: T) -> T
+    where
+        T: AsRef<[u8]>,
+    {
+        self.write(left.as_ref());
+        self.write(right.as_ref());
+        self.hash()
+    }
+
+    /// Combine an ordered slice of child hashes into their parent hash. This generalizes
+    /// `node` from binary trees to arbitrary arity `k`: the parent of a k-ary node is the
+    /// hash of its `k` children's bytes, concatenated in order.
+    fn multi_node(&mut self, nodes: &[T]) -> T
+    where
+        T: AsRef<[u8]>,
+    {
+        for node in nodes {
+            self.write(node.as_ref());
+        }
+        self.hash()
+    }
+}
@@ -1,27 +1,122 @@
 use crate::hash::Algorithm;
 use parity_codec::{Encode, Decode};
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+/// Domain tag prepended before hashing a leaf, so a leaf hash can never be replayed as an
+/// interior node hash.
+const LEAF_TAG: u8 = 0x00;
+
+/// Domain tag prepended before hashing a pair of children, so an interior node hash can
+/// never be replayed as a leaf hash.
+const NODE_TAG: u8 = 0x01;
+
+/// Errors produced while validating a [`Proof`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ProofError {
+    /// The proof's path is longer than the caller-supplied maximum tree depth.
+    MaxDepthExceeded,
+}
+
+/// Computes the domain-separated hash of an ordered slice of children,
+/// `H(0x01 || child_0 || child_1 || ... )`.
+///
+/// Tree builders that want their proofs to verify under [`Proof::validate_tagged`] must
+/// combine children this way instead of with `Algorithm::node`/`Algorithm::multi_node`.
+pub fn tagged_node<A, T>(a: &mut A, children: &[T]) -> T
+where
+    A: Algorithm<T>,
+    T: AsRef<[u8]>,
+{
+    a.reset();
+    a.write(&[NODE_TAG]);
+    for child in children {
+        a.write(child.as_ref());
+    }
+    a.hash()
+}
+
+/// Computes the domain-separated leaf hash, `H(0x00 || data)`.
+///
+/// Tree builders that want their proofs to verify under [`Proof::validate_tagged`] must
+/// hash their leaves this way instead of with `Algorithm::leaf`.
+pub fn tagged_leaf<A, T>(a: &mut A, data: &[u8]) -> T
+where
+    A: Algorithm<T>,
+{
+    a.reset();
+    a.write(&[LEAF_TAG]);
+    a.write(data);
+    a.hash()
+}
 
 /// Merkle tree inclusion proof for data element, for which item = Leaf(Hash(Data Item)).
 ///
-/// Lemma layout:
+/// `Proof` supports trees of arbitrary arity `k` (binary trees being the `k == 2` case).
+/// Lemma layout, grouped by level, is the target item followed by each level's `k - 1`
+/// siblings in left-to-right order, ending in the root:
 ///
 /// ```text
-/// [ item h1x h2y h3z ... root ]
+/// [ item (level 0 siblings)... (level 1 siblings)... ... root ]
 /// ```
 ///
-/// Proof validation is positioned hash against lemma path to match root hash.
+/// `path[i]` is the branch index (`0..arity`) of the running hash among its siblings at
+/// level `i`. Proof validation re-inserts the running hash at that position and hashes the
+/// resulting `arity`-sized group to get the parent, walking up to the root.
 #[derive(Debug, Clone, Eq, PartialEq, Encode, Decode)]
 pub struct Proof<T: Eq + Clone + AsRef<[u8]> + Encode + Decode> {
     lemma: Vec<T>,
-    path: Vec<bool>,
+    path: Vec<usize>,
+    arity: usize,
 }
 
+/// A binary (arity-2) Merkle tree proof. This is the same type as [`Proof`]; binary proofs
+/// are built with [`Proof::new_binary`] or with [`Proof::new`] and an arity of `2`.
+pub type BinaryProof<T> = Proof<T>;
+
 impl<T: Eq + Clone + AsRef<[u8]> + Encode + Decode> Proof<T> {
-    /// Creates new MT inclusion proof
-    pub fn new(hash: Vec<T>, path: Vec<bool>) -> Proof<T> {
+    /// Creates a new MT inclusion proof for a tree of the given `arity`.
+    ///
+    /// `path[i]` is the branch index (`0..arity`) of the target within its parent at level
+    /// `i`, and `hash` is `[item, level_0_siblings..., level_1_siblings..., ..., root]`
+    /// with `arity - 1` siblings per level.
+    pub fn new(hash: Vec<T>, path: Vec<usize>, arity: usize) -> Proof<T> {
+        assert!(arity >= 2);
         assert!(hash.len() > 2);
-        assert_eq!(hash.len() - 2, path.len());
-        Proof { lemma: hash, path }
+        let levels = path.len();
+        assert_eq!(hash.len(), 1 + levels * (arity - 1) + 1);
+        assert!(path.iter().all(|&branch| branch < arity));
+        Proof { lemma: hash, path, arity }
+    }
+
+    /// Creates a new binary (arity-2) MT inclusion proof, preserving the original
+    /// `Vec<bool>` path representation (`true` meaning "running hash is on the left").
+    pub fn new_binary(hash: Vec<T>, path: Vec<bool>) -> Proof<T> {
+        let path = path.into_iter().map(|left| if left { 0 } else { 1 }).collect();
+        Self::new(hash, path, 2)
+    }
+
+    /// Creates a new MT inclusion proof whose leaf is hashed through [`tagged_leaf`] instead
+    /// of being supplied pre-hashed.
+    ///
+    /// `rest` is the lemma tail after the leaf: `[level_0_siblings..., ..., root]`, exactly
+    /// what [`Proof::new`] expects after its first element. Proofs built this way are the
+    /// only ones [`Proof::validate_tagged_with_data`] can bind to their data — a lemma whose
+    /// leaf slot was not produced by `tagged_leaf` has no such guarantee.
+    pub fn new_tagged<A: Algorithm<T>, D: AsRef<[u8]>>(
+        data: &D,
+        rest: Vec<T>,
+        path: Vec<usize>,
+        arity: usize,
+    ) -> Proof<T> {
+        let mut a = A::default();
+        let item = tagged_leaf(&mut a, data.as_ref());
+
+        let mut hash = Vec::with_capacity(rest.len() + 1);
+        hash.push(item);
+        hash.extend(rest);
+
+        Self::new(hash, path, arity)
     }
 
     /// Return proof target leaf
@@ -34,35 +129,126 @@ impl<T: Eq + Clone + AsRef<[u8]> + Encode + Decode> Proof<T> {
         self.lemma.last().unwrap().clone()
     }
 
+    /// Return the tree arity this proof was built for.
+    pub fn arity(&self) -> usize {
+        self.arity
+    }
+
+    /// Builds the `arity`-sized ordered child list for level `i`, inserting `h` (the running
+    /// hash coming up from below) at its branch position among that level's siblings.
+    fn children_at(&self, level: usize, h: &T) -> Vec<T> {
+        let siblings_per_level = self.arity - 1;
+        let start = 1 + level * siblings_per_level;
+        let mut siblings = self.lemma[start..start + siblings_per_level].iter().cloned();
+
+        (0..self.arity)
+            .map(|slot| if slot == self.path[level] { h.clone() } else { siblings.next().unwrap() })
+            .collect()
+    }
+
     /// Verifies MT inclusion proof
     pub fn validate<A: Algorithm<T>>(&self) -> bool {
-        let size = self.lemma.len();
-        if size < 2 {
+        if self.lemma.len() < 2 {
             return false;
         }
 
         let mut h = self.item();
         let mut a = A::default();
 
-        for i in 1..size - 1 {
+        for level in 0..self.path.len() {
+            let children = self.children_at(level, &h);
             a.reset();
-            h = if self.path[i - 1] {
-                a.node(h, self.lemma[i].clone())
-            } else {
-                a.node(self.lemma[i].clone(), h)
-            };
+            h = a.multi_node(&children);
         }
 
         h == self.root()
     }
 
+    /// Verifies MT inclusion proof using domain-separated interior-node hashing.
+    ///
+    /// This combines children with [`tagged_node`] so that interior node hashes live in a
+    /// different domain from the leaf hashes [`tagged_leaf`] produces. `max_depth` bounds the
+    /// number of levels this proof is allowed to walk; proofs deeper than that are rejected
+    /// outright with [`ProofError::MaxDepthExceeded`] rather than silently verified.
+    ///
+    /// This alone does **not** close the second-preimage attack the tagged scheme exists
+    /// for: it walks from `self.item()` as given, so an attacker who controls the lemma can
+    /// still supply an `item()` equal to some interior node's tagged hash. Callers who do
+    /// not already trust `item()` from some other binding must use
+    /// [`Proof::validate_tagged_with_data`] instead, which additionally constrains the leaf
+    /// to `tagged_leaf(data)`.
+    pub fn validate_tagged<A: Algorithm<T>>(&self, max_depth: usize) -> Result<bool, ProofError> {
+        if self.path.len() > max_depth {
+            return Err(ProofError::MaxDepthExceeded);
+        }
+
+        if self.lemma.len() < 2 {
+            return Ok(false);
+        }
+
+        let mut h = self.item();
+        let mut a = A::default();
+
+        for level in 0..self.path.len() {
+            let children = self.children_at(level, &h);
+            h = tagged_node(&mut a, &children);
+        }
+
+        Ok(h == self.root())
+    }
+
+    /// Verifies MT inclusion proof using domain-separated leaf/node hashing, binding the
+    /// leaf to `data` via [`tagged_leaf`] so that a forged `item()` equal to some interior
+    /// node's tagged hash cannot pass as a leaf: the leaf slot must be `H(0x00 || data)` for
+    /// the caller's own `data`, which the interior-node tag `0x01` can never produce no
+    /// matter what bytes an attacker packs into `data`.
+    pub fn validate_tagged_with_data<A: Algorithm<T>, D: AsRef<[u8]>>(
+        &self,
+        data: &D,
+        max_depth: usize,
+    ) -> Result<bool, ProofError> {
+        let mut a = A::default();
+        if tagged_leaf(&mut a, data.as_ref()) != self.item() {
+            return Ok(false);
+        }
+
+        self.validate_tagged::<A>(max_depth)
+    }
+
+    /// Verifies that `data` is the actual data item this proof's leaf was computed from,
+    /// then runs the normal lemma walk.
+    ///
+    /// This is the common "this data is in the tree" check in one call: without it, callers
+    /// have to hash `data` themselves with the same `Algorithm` and compare against
+    /// [`Proof::item`] before calling [`Proof::validate`].
+    pub fn validate_with_data<A: Algorithm<T>, D: AsRef<[u8]>>(&self, data: &D) -> bool {
+        let mut a = A::default();
+        a.write(data.as_ref());
+        let h = a.hash();
+        let leaf = a.leaf(h);
+
+        leaf == self.item() && self.validate::<A>()
+    }
+
+    /// Like [`Proof::validate_with_data`], but additionally checks that this proof's root
+    /// matches a caller-supplied `expected_root` — the "this data is in the tree with root
+    /// R" check reliable-broadcast style consumers need when matching received shards
+    /// against an advertised root hash.
+    pub fn validate_with_root<A: Algorithm<T>, D: AsRef<[u8]>>(
+        &self,
+        data: &D,
+        expected_root: &T,
+    ) -> bool {
+        self.root() == *expected_root && self.validate_with_data::<A, D>(data)
+    }
+
     /// Get lemma
     pub fn lemma(&self) -> Vec<T> {
         self.lemma.clone()
     }
 
     /// Get path
-    pub fn path(&self) -> Vec<bool> {
+    pub fn path(&self) -> Vec<usize> {
         self.path.clone()
     }
 
@@ -76,3 +262,458 @@ impl<T: Eq + Clone + AsRef<[u8]> + Encode + Decode> Proof<T> {
         Decode::decode(&mut &bytes[..]).ok_or(())
     }
 }
+
+/// A tree position paired with its hash, ordered by position alone so it can seed a
+/// max-heap.
+///
+/// Positions follow the Complete-Binary-Merkle-Tree numbering: the root is `1`, and node
+/// `p`'s children are `2p` (left) and `2p + 1` (right).
+///
+/// `Eq`/`Ord` are both defined purely in terms of `position` (not `hash`) so the two stay
+/// consistent, as `Ord`'s contract requires. Positions are unique within a single
+/// `validate`/`new` heap, so this is equivalent in practice to comparing both fields, but
+/// only comparing `position` keeps that an invariant rather than an accident.
+#[derive(Debug, Clone)]
+struct PositionedHash<T> {
+    position: usize,
+    hash: T,
+}
+
+impl<T> PartialEq for PositionedHash<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.position == other.position
+    }
+}
+
+impl<T> Eq for PositionedHash<T> {}
+
+impl<T> Ord for PositionedHash<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.position.cmp(&other.position)
+    }
+}
+
+impl<T> PartialOrd for PositionedHash<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// A complete binary Merkle tree's hashes, addressed by the same position numbering
+/// [`MultiProof`] uses: the root is `1`, node `p`'s children are `2p` and `2p + 1`, and an
+/// `n`-leaf tree has its leaves at positions `[n, 2n)`.
+pub struct CompleteBinaryTree<T> {
+    /// `nodes[p]` is the hash at position `p`; `nodes[0]` is unused padding so positions can
+    /// be used directly as indices.
+    nodes: Vec<T>,
+    leaf_count: usize,
+}
+
+impl<T: Clone> CompleteBinaryTree<T> {
+    /// Builds a tree from `nodes[1..]`, the hash at every position from the root down to
+    /// the leaves. `nodes.len()` must be `2 * n` with `n` a power of two, i.e. a perfect
+    /// binary tree's worth of positions plus one padding slot at index `0` — the position
+    /// scheme (root `1`, children `2p`/`2p + 1`) is only valid for a perfect tree; anything
+    /// else yields a malformed tree and wrong `MultiProof`s.
+    pub fn new(nodes: Vec<T>) -> CompleteBinaryTree<T> {
+        assert!(nodes.len() >= 2);
+        assert_eq!(nodes.len() % 2, 0);
+        let leaf_count = nodes.len() / 2;
+        assert!(leaf_count.is_power_of_two());
+        CompleteBinaryTree { nodes, leaf_count }
+    }
+
+    fn at(&self, position: usize) -> T {
+        self.nodes[position].clone()
+    }
+}
+
+/// Batch inclusion proof for several leaves of one binary Merkle tree against a single
+/// root, sharing each interior hash the opened leaves have in common instead of repeating
+/// it once per leaf as N independent [`Proof`]s would.
+#[derive(Debug, Clone, Eq, PartialEq, Encode, Decode)]
+pub struct MultiProof<T: Eq + Clone + AsRef<[u8]> + Encode + Decode> {
+    /// Opened leaves as `(position, hash)`, sorted and de-duplicated by position. Positions
+    /// use the Complete-Binary-Merkle-Tree numbering (root `1`, node `p`'s children `2p`
+    /// and `2p + 1`), so a tree of `n` leaves has them at positions `[n, 2n)`.
+    leaves: Vec<(usize, T)>,
+    /// Sibling hashes not already covered by another opened leaf, in decreasing position
+    /// order: the same order `validate`'s max-heap pops them in as it walks up from the
+    /// leaves.
+    lemma: Vec<T>,
+    root: T,
+}
+
+impl<T: Eq + Clone + AsRef<[u8]> + Encode + Decode> MultiProof<T> {
+    /// Builds a multi-leaf inclusion proof for `indices` (sorted, de-duplicated leaf
+    /// indices within `tree`) against the full `tree`.
+    ///
+    /// Walks the same position-keyed max-heap algorithm [`MultiProof::validate`] uses,
+    /// except over bare positions: whenever a popped position's sibling is not itself
+    /// about to be opened by another requested leaf, its hash is read out of `tree` and
+    /// appended to `lemma`. This emits each shared interior hash at most once and in
+    /// exactly the order `validate` will need it.
+    pub fn new(indices: &[usize], tree: &CompleteBinaryTree<T>) -> MultiProof<T> {
+        assert!(!indices.is_empty());
+        assert!(indices.windows(2).all(|w| w[0] < w[1]));
+        assert!(indices.iter().all(|&i| i < tree.leaf_count));
+
+        let positions: Vec<usize> = indices.iter().map(|&i| tree.leaf_count + i).collect();
+        let leaves: Vec<(usize, T)> =
+            positions.iter().map(|&position| (position, tree.at(position))).collect();
+
+        let mut heap: BinaryHeap<usize> = positions.iter().cloned().collect();
+        let mut lemma = Vec::new();
+
+        while let Some(position) = heap.pop() {
+            if position == 1 {
+                break;
+            }
+
+            let sibling_position = position ^ 1;
+            if heap.peek() == Some(&sibling_position) {
+                heap.pop();
+            } else {
+                lemma.push(tree.at(sibling_position));
+            }
+
+            heap.push(position / 2);
+        }
+
+        MultiProof { leaves, lemma, root: tree.at(1) }
+    }
+
+    /// Return the opened leaf hashes, in position order.
+    pub fn items(&self) -> Vec<T> {
+        self.leaves.iter().map(|(_, hash)| hash.clone()).collect()
+    }
+
+    /// Return tree root
+    pub fn root(&self) -> T {
+        self.root.clone()
+    }
+
+    /// Verifies that every opened leaf is included in the tree with this root.
+    ///
+    /// Walks bottom-up with a position-keyed max-heap: seeded with `(position, hash)` for
+    /// each opened leaf, it repeatedly pops the highest position `p` and pairs it with its
+    /// sibling, either from elsewhere in the heap (another opened leaf/already-computed
+    /// parent sharing that subtree) or, failing that, the next hash from `lemma`. The pair
+    /// hashes to the parent at `p / 2`, which is pushed back onto the heap; this continues
+    /// until position `1` (the root) is produced.
+    pub fn validate<A: Algorithm<T>>(&self) -> bool {
+        let mut heap: BinaryHeap<PositionedHash<T>> = self
+            .leaves
+            .iter()
+            .map(|(position, hash)| PositionedHash { position: *position, hash: hash.clone() })
+            .collect();
+        let mut lemma = self.lemma.iter().cloned();
+        let mut a = A::default();
+
+        loop {
+            let top = match heap.pop() {
+                Some(top) => top,
+                None => return false,
+            };
+
+            if top.position == 1 {
+                return heap.is_empty() && top.hash == self.root;
+            }
+
+            let sibling_position = top.position ^ 1;
+            let sibling_hash = match heap.peek() {
+                Some(next) if next.position == sibling_position => heap.pop().unwrap().hash,
+                _ => match lemma.next() {
+                    Some(hash) => hash,
+                    None => return false,
+                },
+            };
+
+            let (left, right) = if top.position % 2 == 0 {
+                (top.hash, sibling_hash)
+            } else {
+                (sibling_hash, top.hash)
+            };
+
+            a.reset();
+            let parent = a.node(left, right);
+            heap.push(PositionedHash { position: top.position / 2, hash: parent });
+        }
+    }
+
+    /// Turns a proof into the raw bytes.
+    pub fn into_bytes(&self) -> Vec<u8> {
+        self.encode()
+    }
+
+    /// Tries to parse `bytes` into proof.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ()> {
+        Decode::decode(&mut &bytes[..]).ok_or(())
+    }
+}
+
+/// Sentinel hash for an empty (never-written) subtree in a [`SparseProof`].
+///
+/// Uses the hash type's default value; a sparse tree's empty leaves and unset subtrees all
+/// collapse to this one value, rather than each being hashed individually.
+pub fn empty_hash<T: Default>() -> T {
+    T::default()
+}
+
+/// Fixed-depth inclusion/exclusion proof for a sparse Merkle tree keyed by bit path.
+///
+/// A sparse tree has one leaf slot per possible key; slots that were never written hash to
+/// [`empty_hash`]. Walking from a target slot to the root with the sibling at each level
+/// (substituting [`empty_hash`] for siblings whose subtree is unset) reconstructs the root
+/// the same way [`Proof::validate`]'s positioned-hash walk does, which lets one proof type
+/// serve both membership (`validate_presence`) and non-membership (`validate_absence`).
+#[derive(Debug, Clone, Eq, PartialEq, Encode, Decode)]
+pub struct SparseProof<T: Eq + Clone + AsRef<[u8]> + Encode + Decode + Default> {
+    /// Branch bit at each level from the target slot up to the root (`false` = slot is the
+    /// left child, `true` = slot is the right child), index `0` being the level nearest the
+    /// leaf.
+    key_bits: Vec<bool>,
+    /// Sibling hash at each level, in the same leaf-to-root order as `key_bits`; a sibling
+    /// equal to [`empty_hash`] marks that subtree as unset.
+    siblings: Vec<T>,
+    root: T,
+}
+
+impl<T: Eq + Clone + AsRef<[u8]> + Encode + Decode + Default> SparseProof<T> {
+    /// Creates a new sparse-tree proof for a key path of `key_bits.len()` levels.
+    pub fn new(key_bits: Vec<bool>, siblings: Vec<T>, root: T) -> SparseProof<T> {
+        assert_eq!(key_bits.len(), siblings.len());
+        SparseProof { key_bits, siblings, root }
+    }
+
+    /// Return tree root
+    pub fn root(&self) -> T {
+        self.root.clone()
+    }
+
+    /// Reconstructs the root by walking from `leaf` up through each level's sibling.
+    fn walk<A: Algorithm<T>>(&self, leaf: T) -> T {
+        let mut h = leaf;
+        let mut a = A::default();
+
+        for (bit, sibling) in self.key_bits.iter().zip(self.siblings.iter()) {
+            // Two unset subtrees make an unset parent, which is `empty_hash` itself.
+            if h == empty_hash() && *sibling == empty_hash() {
+                continue;
+            }
+            a.reset();
+            h = if *bit {
+                a.node(sibling.clone(), h)
+            } else {
+                a.node(h, sibling.clone())
+            };
+        }
+
+        h
+    }
+
+    /// Verifies that the key this proof's path addresses is unoccupied, i.e. its slot and
+    /// every sibling subtree combine, starting from [`empty_hash`], to this proof's root.
+    pub fn validate_absence<A: Algorithm<T>>(&self) -> bool {
+        self.walk::<A>(empty_hash()) == self.root
+    }
+
+    /// Verifies that `value` (the leaf hash) is stored at the key this proof's path
+    /// addresses, i.e. it combines with every sibling subtree to this proof's root.
+    pub fn validate_presence<A: Algorithm<T>>(&self, value: T) -> bool {
+        self.walk::<A>(value) == self.root
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::hash::Hasher;
+
+    /// A small FNV-1a style [`Algorithm`] used only to exercise proof round-trips; not
+    /// cryptographically meaningful.
+    #[derive(Default)]
+    struct TestAlgorithm(Vec<u8>);
+
+    impl Hasher for TestAlgorithm {
+        fn finish(&self) -> u64 {
+            let mut hash: u64 = 0xcbf29ce484222325;
+            for byte in &self.0 {
+                hash ^= u64::from(*byte);
+                hash = hash.wrapping_mul(0x100000001b3);
+            }
+            hash
+        }
+
+        fn write(&mut self, bytes: &[u8]) {
+            self.0.extend_from_slice(bytes);
+        }
+    }
+
+    impl Algorithm<Vec<u8>> for TestAlgorithm {
+        fn hash(&mut self) -> Vec<u8> {
+            self.finish().to_le_bytes().to_vec()
+        }
+    }
+
+    fn leaf_hash(data: &[u8]) -> Vec<u8> {
+        let mut a = TestAlgorithm::default();
+        a.write(data);
+        let h = a.hash();
+        a.leaf(h)
+    }
+
+    #[test]
+    fn validate_round_trip() {
+        let l0 = leaf_hash(b"a");
+        let l1 = leaf_hash(b"b");
+        let l2 = leaf_hash(b"c");
+        let l3 = leaf_hash(b"d");
+
+        let mut a = TestAlgorithm::default();
+        let n0 = a.multi_node(&[l0.clone(), l1.clone()]);
+        a.reset();
+        let n1 = a.multi_node(&[l2, l3]);
+        a.reset();
+        let root = a.multi_node(&[n0.clone(), n1.clone()]);
+
+        let proof = Proof::new(vec![l0, l1, n1, root], vec![0, 0], 2);
+        assert!(proof.validate::<TestAlgorithm>());
+
+        let mut tampered = proof.lemma();
+        tampered[1] = leaf_hash(b"not b");
+        let tampered = Proof::new(tampered, proof.path(), 2);
+        assert!(!tampered.validate::<TestAlgorithm>());
+    }
+
+    #[test]
+    fn new_binary_round_trip() {
+        let l0 = leaf_hash(b"a");
+        let l1 = leaf_hash(b"b");
+
+        let mut a = TestAlgorithm::default();
+        let root = a.multi_node(&[l0.clone(), l1.clone()]);
+
+        let proof = Proof::new_binary(vec![l0, l1, root], vec![true]);
+        assert!(proof.validate::<TestAlgorithm>());
+
+        let wrong_path = Proof::new_binary(proof.lemma(), vec![false]);
+        assert!(!wrong_path.validate::<TestAlgorithm>());
+    }
+
+    #[test]
+    fn kary_validate_round_trip() {
+        let l0 = leaf_hash(b"a");
+        let l1 = leaf_hash(b"b");
+        let l2 = leaf_hash(b"c");
+
+        let mut a = TestAlgorithm::default();
+        let root = a.multi_node(&[l0.clone(), l1.clone(), l2.clone()]);
+
+        let proof = Proof::new(vec![l0, l1, l2, root], vec![0], 3);
+        assert!(proof.validate::<TestAlgorithm>());
+
+        let mut tampered = proof.lemma();
+        tampered[2] = leaf_hash(b"not c");
+        let tampered = Proof::new(tampered, proof.path(), 3);
+        assert!(!tampered.validate::<TestAlgorithm>());
+    }
+
+    #[test]
+    fn validate_tagged_round_trip() {
+        let data0 = b"a".to_vec();
+        let data1 = b"b".to_vec();
+
+        let mut a = TestAlgorithm::default();
+        let leaf0 = tagged_leaf(&mut a, &data0);
+        let leaf1 = tagged_leaf(&mut a, &data1);
+        let root = tagged_node(&mut a, &[leaf0, leaf1.clone()]);
+
+        let proof = Proof::new_tagged::<TestAlgorithm, _>(&data0, vec![leaf1, root], vec![0], 2);
+        assert!(proof.validate_tagged::<TestAlgorithm>(8).unwrap());
+        assert!(proof.validate_tagged_with_data::<TestAlgorithm, _>(&data0, 8).unwrap());
+        assert!(!proof.validate_tagged_with_data::<TestAlgorithm, _>(&data1, 8).unwrap());
+
+        assert_eq!(
+            proof.validate_tagged::<TestAlgorithm>(0).unwrap_err(),
+            ProofError::MaxDepthExceeded
+        );
+    }
+
+    #[test]
+    fn validate_with_data_round_trip() {
+        let data0 = b"a".to_vec();
+        let data1 = b"b".to_vec();
+
+        let leaf0 = leaf_hash(&data0);
+        let leaf1 = leaf_hash(&data1);
+
+        let mut a = TestAlgorithm::default();
+        let root = a.multi_node(&[leaf0.clone(), leaf1.clone()]);
+
+        let proof = Proof::new_binary(vec![leaf0, leaf1, root.clone()], vec![true]);
+        assert!(proof.validate_with_data::<TestAlgorithm, _>(&data0));
+        assert!(!proof.validate_with_data::<TestAlgorithm, _>(&data1));
+
+        assert!(proof.validate_with_root::<TestAlgorithm, _>(&data0, &root));
+        let wrong_root = leaf_hash(b"not the root");
+        assert!(!proof.validate_with_root::<TestAlgorithm, _>(&data0, &wrong_root));
+    }
+
+    #[test]
+    fn multi_proof_round_trip() {
+        let leaves: Vec<Vec<u8>> =
+            [b"a".as_ref(), b"b".as_ref(), b"c".as_ref(), b"d".as_ref()]
+                .iter()
+                .map(|d| leaf_hash(d))
+                .collect();
+
+        let mut a = TestAlgorithm::default();
+        let n2 = a.node(leaves[0].clone(), leaves[1].clone());
+        a.reset();
+        let n3 = a.node(leaves[2].clone(), leaves[3].clone());
+        a.reset();
+        let n1 = a.node(n2.clone(), n3.clone());
+
+        let nodes = vec![n1.clone(), n1, n2, n3, leaves[0].clone(), leaves[1].clone(), leaves[2].clone(), leaves[3].clone()];
+        let tree = CompleteBinaryTree::new(nodes);
+
+        let proof = MultiProof::new(&[0, 2], &tree);
+        assert!(proof.validate::<TestAlgorithm>());
+
+        let mut tampered = proof.clone();
+        tampered.leaves[0].1 = leaf_hash(b"not a");
+        assert!(!tampered.validate::<TestAlgorithm>());
+    }
+
+    #[test]
+    fn sparse_proof_round_trip() {
+        let value = leaf_hash(b"leaf value");
+        let sibling0 = leaf_hash(b"sibling 0");
+        let sibling1 = leaf_hash(b"sibling 1");
+        let key_bits = vec![false, true];
+
+        let mut a = TestAlgorithm::default();
+        let mut h = value.clone();
+        h = a.node(h, sibling0.clone());
+        a.reset();
+        h = a.node(sibling1.clone(), h);
+        let root = h;
+
+        let proof = SparseProof::new(key_bits.clone(), vec![sibling0.clone(), sibling1.clone()], root);
+        assert!(proof.validate_presence::<TestAlgorithm>(value));
+        assert!(!proof.validate_presence::<TestAlgorithm>(leaf_hash(b"wrong value")));
+        assert!(!proof.validate_absence::<TestAlgorithm>());
+
+        let mut a = TestAlgorithm::default();
+        let empty = empty_hash::<Vec<u8>>();
+        let mut h = empty.clone();
+        h = a.node(h, sibling0.clone());
+        a.reset();
+        h = a.node(sibling1.clone(), h);
+        let absence_root = h;
+
+        let absence_proof = SparseProof::new(key_bits, vec![sibling0, sibling1], absence_root);
+        assert!(absence_proof.validate_absence::<TestAlgorithm>());
+    }
+}